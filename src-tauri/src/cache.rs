@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+
+/// Computes the content-addressed cache key for converting a file's bytes into
+/// `output_format` with `options`: a SHA-256 of the source bytes combined with the
+/// conversion params, so different quality settings don't share a cache entry.
+pub fn cache_key(file_bytes: &[u8], output_format: &str, options: &crate::options::ConversionOptions) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(file_bytes);
+    hasher.update(output_format.to_lowercase().as_bytes());
+    hasher.update(options.cache_fingerprint().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Path a cached conversion for `key`/`output_format` would live at, creating the
+/// cache directory if it doesn't exist yet.
+pub fn cached_output_path(app: &tauri::AppHandle, key: &str, output_format: &str) -> Result<PathBuf, String> {
+    let cache_dir = app.path().app_cache_dir().map_err(|e| e.to_string())?.join("conversions");
+    std::fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    Ok(cache_dir.join(format!("{}.{}", key, output_format.to_lowercase())))
+}
+
+/// Returns the path to a previously produced conversion for `key`, if one exists.
+pub fn lookup(app: &tauri::AppHandle, key: &str, output_format: &str) -> Result<Option<PathBuf>, String> {
+    let path = cached_output_path(app, key, output_format)?;
+    Ok(path.exists().then_some(path))
+}
+
+/// Temp path to produce a conversion into before it's renamed to its final cache
+/// path, suffixed with `job_id` so concurrent conversions of the same input never
+/// write over each other. Keeping writes out of the final path means a failed or
+/// cancelled conversion never leaves a corrupt file that `lookup` would serve as a
+/// permanent cache hit.
+pub fn temp_output_path(app: &tauri::AppHandle, key: &str, job_id: &str) -> Result<PathBuf, String> {
+    let cache_dir = app.path().app_cache_dir().map_err(|e| e.to_string())?.join("conversions");
+    std::fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    Ok(cache_dir.join(format!("{}.{}.tmp", key, job_id)))
+}
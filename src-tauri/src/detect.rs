@@ -0,0 +1,80 @@
+use std::path::Path;
+
+/// Result of sniffing a file's real format from its magic bytes and comparing it
+/// against the extension the caller claimed.
+#[derive(Debug, Clone, Default)]
+pub struct DetectedType {
+    pub mime_type: Option<String>,
+    pub extension: Option<String>,
+    pub mismatch_warning: Option<String>,
+}
+
+/// Sniffs `file_bytes` with `infer` and flags a mismatch against `file_path`'s extension.
+/// Text-based formats (svg, txt, md, ...) have no magic bytes `infer` recognizes, so
+/// `extension`/`mime_type` fall back to `None` and callers should trust the claimed extension.
+pub fn sniff(file_path: &str, file_bytes: &[u8]) -> DetectedType {
+    let claimed_ext = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|s| s.to_lowercase());
+
+    let Some(kind) = infer::get(file_bytes) else {
+        return DetectedType::default();
+    };
+
+    let extension = kind.extension().to_string();
+    let mime_type = kind.mime_type().to_string();
+
+    let mismatch_warning = match &claimed_ext {
+        Some(claimed) if !extensions_agree(claimed, &extension) => Some(format!(
+            "File extension .{} does not match detected format .{}",
+            claimed, extension
+        )),
+        _ => None,
+    };
+
+    DetectedType { mime_type: Some(mime_type), extension: Some(extension), mismatch_warning }
+}
+
+/// `infer` normalizes some extensions differently than users/filesystems do
+/// (e.g. "jpg" vs "jpeg", "tiff" vs "tif"); treat those pairs as agreeing.
+fn extensions_agree(claimed: &str, detected: &str) -> bool {
+    claimed == detected
+        || matches!(
+            (claimed, detected),
+            ("jpg", "jpeg") | ("jpeg", "jpg") | ("tiff", "tif") | ("tif", "tiff")
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Little-endian TIFF header (byte order marker, magic number, first-IFD offset)
+    // padded with zeros: `infer` also inspects bytes past the magic number (to rule
+    // out CR2), so a buffer holding only the 4 magic bytes is not recognized.
+    const TIFF_MAGIC: &[u8] = &[
+        0x49, 0x49, 0x2A, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn tiff_extension_does_not_trip_a_mismatch_warning() {
+        let detection = sniff("scan.tiff", TIFF_MAGIC);
+        assert_eq!(detection.extension.as_deref(), Some("tif"));
+        assert!(detection.mismatch_warning.is_none());
+    }
+
+    #[test]
+    fn tif_extension_does_not_trip_a_mismatch_warning() {
+        let detection = sniff("scan.tif", TIFF_MAGIC);
+        assert!(detection.mismatch_warning.is_none());
+    }
+
+    #[test]
+    fn a_genuine_mismatch_still_warns() {
+        // PNG magic bytes claimed as a .jpg
+        let png_magic: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+        let detection = sniff("photo.jpg", png_magic);
+        assert!(detection.mismatch_warning.is_some());
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::path::Path;
+
+/// Broad category of media, used to decide which output formats are reachable
+/// from a given input without ever invoking FFmpeg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Audio,
+    Video,
+    Document,
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MediaKind::Image => "image",
+            MediaKind::Audio => "audio",
+            MediaKind::Video => "video",
+            MediaKind::Document => "document",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl MediaKind {
+    /// Classifies a file extension (case-insensitive, no leading dot) into a [`MediaKind`].
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "png" | "jpg" | "jpeg" | "webp" | "gif" | "bmp" | "tiff" | "tif" | "ico" | "svg" => {
+                Some(MediaKind::Image)
+            }
+            "mp3" | "wav" | "flac" | "ogg" | "aac" | "m4a" | "wma" => Some(MediaKind::Audio),
+            "mp4" | "mkv" | "mov" | "avi" | "webm" | "flv" | "wmv" => Some(MediaKind::Video),
+            "pdf" | "docx" | "txt" | "md" => Some(MediaKind::Document),
+            _ => None,
+        }
+    }
+
+    /// Extensions FFmpeg (or our special-cased branches) can produce from this kind.
+    fn compatible_outputs(self) -> &'static [&'static str] {
+        match self {
+            MediaKind::Image => &["png", "jpg", "jpeg", "webp", "gif", "bmp", "tiff", "tif", "ico", "svg"],
+            MediaKind::Audio => &["mp3", "wav", "flac", "ogg", "aac", "m4a"],
+            MediaKind::Video => &["mp4", "mkv", "mov", "avi", "webm"],
+            MediaKind::Document => &["pdf", "txt", "md"],
+        }
+    }
+}
+
+/// Error produced while validating a requested conversion, before FFmpeg is ever spawned.
+#[derive(Debug)]
+pub enum FormatError {
+    UnknownExtension(String),
+    Incompatible { from: MediaKind, to: String },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::UnknownExtension(ext) => {
+                write!(f, "Unsupported input extension: .{}", ext)
+            }
+            FormatError::Incompatible { from, to } => {
+                write!(f, "Cannot convert {} input to .{}", from, to)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Classifies `input_path` and checks that `output_format` is reachable from it,
+/// returning the detected [`MediaKind`] on success. `sniffed_extension`, when present,
+/// is the content-sniffed extension and takes priority over the one on `input_path`.
+pub fn validate_conversion(
+    input_path: &str,
+    output_format: &str,
+    sniffed_extension: Option<&str>,
+) -> Result<MediaKind, FormatError> {
+    let ext = sniffed_extension.map(|s| s.to_lowercase()).unwrap_or_else(|| {
+        Path::new(input_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase()
+    });
+    let kind = MediaKind::from_extension(&ext).ok_or_else(|| FormatError::UnknownExtension(ext.clone()))?;
+
+    let output_format = output_format.to_lowercase();
+    if kind.compatible_outputs().contains(&output_format.as_str()) {
+        Ok(kind)
+    } else {
+        Err(FormatError::Incompatible { from: kind, to: output_format })
+    }
+}
+
+/// Lists the output formats reachable from `file_path`'s input format.
+#[tauri::command]
+pub fn list_supported_formats(file_path: String) -> Result<Vec<String>, String> {
+    let ext = Path::new(&file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let kind = MediaKind::from_extension(&ext)
+        .ok_or_else(|| FormatError::UnknownExtension(ext.clone()).to_string())?;
+    Ok(kind.compatible_outputs().iter().map(|s| s.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_a_reachable_conversion() {
+        let kind = validate_conversion("photo.png", "webp", None).expect("png -> webp should be valid");
+        assert_eq!(kind, MediaKind::Image);
+    }
+
+    #[test]
+    fn rejects_an_unknown_input_extension() {
+        let err = validate_conversion("archive.zip", "png", None).unwrap_err();
+        assert!(matches!(err, FormatError::UnknownExtension(ext) if ext == "zip"));
+    }
+
+    #[test]
+    fn rejects_an_incompatible_conversion() {
+        let err = validate_conversion("song.mp3", "png", None).unwrap_err();
+        assert!(matches!(err, FormatError::Incompatible { from: MediaKind::Audio, .. }));
+    }
+
+    #[test]
+    fn sniffed_extension_overrides_the_path_extension() {
+        // A mislabeled .png that's really an .mp3 should validate against mp3, not png:
+        // png -> wav would be rejected, mp3 -> wav is accepted.
+        let kind = validate_conversion("song.png", "wav", Some("mp3")).expect("should use the sniffed extension");
+        assert_eq!(kind, MediaKind::Audio);
+    }
+
+    #[test]
+    fn tif_is_treated_as_an_image_extension() {
+        let kind = validate_conversion("scan.tif", "png", None).expect("tif -> png should be valid");
+        assert_eq!(kind, MediaKind::Image);
+    }
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::detect;
+
+/// Metadata describing an image file, returned by [`read_image_metadata`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    pub color_type: String,
+    pub format: String,
+    pub file_size: u64,
+}
+
+/// Reads an image's dimensions, color type, format and file size without shelling
+/// out to FFmpeg, so the UI can show a fast info panel. The format is sniffed from
+/// content rather than trusted from the extension, matching `convert_file`.
+#[tauri::command]
+pub fn read_image_metadata(file_path: String) -> Result<ImageMetadata, String> {
+    let file_bytes = std::fs::read(&file_path).map_err(|e| e.to_string())?;
+    let detection = detect::sniff(&file_path, &file_bytes);
+    let img = image::load_from_memory(&file_bytes).map_err(|e| e.to_string())?;
+
+    let format = detection.extension.unwrap_or_else(|| {
+        Path::new(&file_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("unknown")
+            .to_lowercase()
+    });
+
+    Ok(ImageMetadata {
+        width: img.width(),
+        height: img.height(),
+        color_type: format!("{:?}", img.color()),
+        format,
+        file_size: file_bytes.len() as u64,
+    })
+}
+
+/// How [`generate_thumbnail`] should fit the source image into the requested box.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFit {
+    /// Preserve aspect ratio, fitting entirely within the box.
+    Contain,
+    /// Preserve aspect ratio, cropping to fill the box exactly.
+    Cover,
+    /// Ignore aspect ratio, stretching to the exact box dimensions.
+    Stretch,
+}
+
+/// The thumbnail [`generate_thumbnail`] wrote to disk.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThumbnailResult {
+    pub thumbnail_path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Content-addressed cache key for a thumbnail: the source bytes combined with the
+/// requested box and fit, so different files (even with the same name) or different
+/// sizes never collide on the same cache path.
+fn thumbnail_key(file_bytes: &[u8], max_w: u32, max_h: u32, fit: ThumbnailFit) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(file_bytes);
+    hasher.update(max_w.to_le_bytes());
+    hasher.update(max_h.to_le_bytes());
+    hasher.update(format!("{:?}", fit).as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Computes the resize operation for `fit` and writes a thumbnail no larger than
+/// `max_w`x`max_h` to the app's cache dir, reusing a previously generated thumbnail
+/// for the same file/box/fit if one already exists.
+#[tauri::command]
+pub fn generate_thumbnail(
+    app: tauri::AppHandle,
+    file_path: String,
+    max_w: u32,
+    max_h: u32,
+    fit: ThumbnailFit,
+) -> Result<ThumbnailResult, String> {
+    let file_bytes = std::fs::read(&file_path).map_err(|e| e.to_string())?;
+    let key = thumbnail_key(&file_bytes, max_w, max_h, fit);
+
+    let cache_dir = app.path().app_cache_dir().map_err(|e| e.to_string())?.join("thumbnails");
+    std::fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    let thumbnail_path = cache_dir.join(format!("{}.png", key));
+
+    if thumbnail_path.exists() {
+        let (width, height) = image::image_dimensions(&thumbnail_path).map_err(|e| e.to_string())?;
+        return Ok(ThumbnailResult { thumbnail_path: thumbnail_path.to_string_lossy().to_string(), width, height });
+    }
+
+    let img = image::load_from_memory(&file_bytes).map_err(|e| e.to_string())?;
+    let thumbnail = match fit {
+        ThumbnailFit::Contain => img.thumbnail(max_w, max_h),
+        ThumbnailFit::Cover => img.resize_to_fill(max_w, max_h, image::imageops::FilterType::Lanczos3),
+        ThumbnailFit::Stretch => img.resize_exact(max_w, max_h, image::imageops::FilterType::Lanczos3),
+    };
+
+    // Written to a temp path and renamed into place only on success, so a crash or
+    // disk-full mid-save never leaves a corrupt file at `thumbnail_path` that the
+    // `exists()` check above would treat as a permanent (and unreadable) cache hit.
+    let temp_path = cache_dir.join(format!("{}.png.{}.tmp", key, std::process::id()));
+    // The `.tmp` suffix names no image format, so the PNG encoder is chosen explicitly.
+    if let Err(e) = thumbnail.save_with_format(&temp_path, image::ImageFormat::Png) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.to_string());
+    }
+    std::fs::rename(&temp_path, &thumbnail_path).map_err(|e| e.to_string())?;
+
+    Ok(ThumbnailResult {
+        thumbnail_path: thumbnail_path.to_string_lossy().to_string(),
+        width: thumbnail.width(),
+        height: thumbnail.height(),
+    })
+}
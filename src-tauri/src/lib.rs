@@ -1,7 +1,27 @@
 use tauri_plugin_shell::ShellExt;
-use tauri::Manager;
+use tauri_plugin_shell::process::CommandEvent;
+use tauri::{Emitter, Manager};
 use std::path::Path;
 
+mod cache;
+mod detect;
+mod formats;
+mod image_info;
+mod options;
+mod progress;
+mod queue;
+
+use options::ConversionOptions;
+use progress::{ConversionDone, ConversionError, ConversionProgress, ConversionWarning, ProgressParser};
+use queue::QueueState;
+
+/// What `convert_file` produced: the output path, and whether metadata was stripped.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConversionResult {
+    pub output_path: String,
+    pub metadata_stripped: bool,
+}
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
 /// A simple greeting command to verify Tauri-React communication.
@@ -10,83 +30,211 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Probes a media file's duration (in seconds) via `ffprobe`, used to turn FFmpeg's
+/// `out_time_ms=` progress field into a percentage.
+async fn probe_duration_secs(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
+    let output = app
+        .shell()
+        .command("ffprobe")
+        .args([
+            "-v", "error",
+            "-show_entries", "format=duration",
+            "-of", "default=noprint_wrapper=1:nokey=1",
+            file_path,
+        ])
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    progress::parse_duration_output(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "Could not determine media duration".to_string())
+}
+
 /// Helper command to convert a media file using FFmpeg.
-/// 
+///
 /// This function:
 /// 1. Takes an input file path and desired output format.
 /// 2. Calculates a temporary output path.
-/// 3. Spawns an FFmpeg sidecar command to perform the conversion.
-/// 4. Returns the path to the converted file upon success.
+/// 3. Spawns an FFmpeg sidecar command, streaming `conversion-progress` events to `job_id`
+///    as the conversion runs.
+/// 4. Emits `conversion-done`/`conversion-error` and returns the path to the converted file
+///    together with whether metadata was stripped.
 #[tauri::command]
-async fn convert_file(app: tauri::AppHandle, file_path: String, output_format: String) -> Result<String, String> {
-    let input_path = Path::new(&file_path);
-    let temp_dir = app.path().temp_dir().map_err(|e| e.to_string())?;
-    
-    let file_stem = input_path.file_stem().ok_or("Invalid file name")?.to_string_lossy();
-    let output_filename = format!("{}.{}", file_stem, output_format.to_lowercase());
-    let output_path = temp_dir.join(&output_filename);
+async fn convert_file(
+    app: tauri::AppHandle,
+    job_id: String,
+    file_path: String,
+    output_format: String,
+    options: Option<ConversionOptions>,
+) -> Result<ConversionResult, String> {
+    let options = options.unwrap_or_default();
+    let result = run_conversion(&app, &job_id, &file_path, &output_format, &options, None).await;
 
+    // The svg branch embeds the source bytes verbatim as a data: URI rather than
+    // going through FFmpeg's -map_metadata, so nothing is actually stripped there.
+    let metadata_stripped = output_format.to_lowercase() != "svg" && options.strips_metadata(&output_format);
+
+    match &result {
+        Ok(output_path) => {
+            let _ = app.emit("conversion-done", ConversionDone { job_id: job_id.clone(), output_path: output_path.clone(), metadata_stripped });
+        }
+        Err(message) => {
+            let _ = app.emit("conversion-error", ConversionError { job_id: job_id.clone(), message: message.clone() });
+        }
+    }
+
+    result.map(|output_path| ConversionResult { metadata_stripped, output_path })
+}
+
+/// Runs one conversion end to end. `queue`, when provided, is given the FFmpeg child
+/// handle so a batch job can be cancelled mid-conversion.
+pub(crate) async fn run_conversion(
+    app: &tauri::AppHandle,
+    job_id: &str,
+    file_path: &str,
+    output_format: &str,
+    options: &ConversionOptions,
+    queue: Option<&QueueState>,
+) -> Result<String, String> {
+    let file_bytes = std::fs::read(file_path).map_err(|e| e.to_string())?;
+    let detection = detect::sniff(file_path, &file_bytes);
+    if let Some(message) = &detection.mismatch_warning {
+        let _ = app.emit("conversion-warning", ConversionWarning { job_id: job_id.to_string(), message: message.clone() });
+    }
+
+    formats::validate_conversion(file_path, output_format, detection.extension.as_deref()).map_err(|e| e.to_string())?;
+
+    let cache_key = cache::cache_key(&file_bytes, output_format, options);
+
+    // A previous run already produced this exact input/format pair — reuse it.
+    if let Some(cached_path) = cache::lookup(app, &cache_key, output_format)? {
+        return Ok(cached_path.to_string_lossy().to_string());
+    }
+
+    let output_path = cache::cached_output_path(app, &cache_key, output_format)?;
     let output_path_str = output_path.to_string_lossy().to_string();
 
+    // Produced into a temp path and renamed into place only on success, so a failed
+    // or cancelled conversion never leaves a corrupt file at the cache path. The
+    // output format's extension is kept last because FFmpeg picks the muxer from it.
+    let temp_path = cache::temp_output_path(app, &cache_key, job_id)?
+        .with_extension(format!("tmp.{}", output_format.to_lowercase()));
+
     // Execute FFmpeg command
     // Note: We use the 'shell' plugin which requires 'ffmpeg' to be allowed in capabilities.
-    
-    let mut args = vec!["-i", &file_path, "-y"];
+
+    let mut args = vec!["-i".to_string(), file_path.to_string(), "-y".to_string()];
 
     // Smart processing for specific formats
     let format_lower = output_format.to_lowercase();
-    
+
     if format_lower == "ico" {
         // ICO requires max 256x256 dimensions. We resize if larger, keeping aspect ratio.
-        args.push("-vf");
-        args.push("scale='min(256,iw)':min'(256,ih)':force_original_aspect_ratio=decrease");
+        args.push("-vf".to_string());
+        args.push("scale='min(256,iw)':min'(256,ih)':force_original_aspect_ratio=decrease".to_string());
     }
 
     if format_lower == "svg" {
         // FFmpeg does not support raster->vector SVG well. We use the 'embedding' strategy.
         // 1. Read the image dimensions
-        let img = image::open(&file_path).map_err(|e| format!("Failed to open image for SVG conversion: {}", e))?;
+        let img = image::open(file_path).map_err(|e| format!("Failed to open image for SVG conversion: {}", e))?;
         let (w, h) = (img.width(), img.height());
 
-        // 2. Read file bytes and encode to base64
-        let file_bytes = std::fs::read(&file_path).map_err(|e| e.to_string())?;
+        // 2. Encode the already-read file bytes to base64
         use base64::{Engine as _, engine::general_purpose};
         let b64 = general_purpose::STANDARD.encode(&file_bytes);
-        
-        let ext = Path::new(&file_path).extension().unwrap_or_default().to_string_lossy().to_lowercase();
-        let mime_type = match ext.as_str() {
-            "png" => "image/png",
-            "jpg" | "jpeg" => "image/jpeg",
-            "webp" => "image/webp",
-            "gif" => "image/gif",
-            _ => "image/png" // Fallback
-        };
+
+        // Prefer the sniffed content type over the extension, which may be wrong.
+        let mime_type = detection.mime_type.clone().unwrap_or_else(|| {
+            let ext = Path::new(file_path).extension().unwrap_or_default().to_string_lossy().to_lowercase();
+            match ext.as_str() {
+                "png" => "image/png",
+                "jpg" | "jpeg" => "image/jpeg",
+                "webp" => "image/webp",
+                "gif" => "image/gif",
+                _ => "image/png", // Fallback
+            }
+            .to_string()
+        });
 
         // 3. Create SVG content
         let svg_content = format!(
             r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">
     <image href="data:{};base64,{}" width="{}" height="{}" />
-</svg>"#, 
+</svg>"#,
             w, h, w, h, mime_type, b64, w, h
         );
 
-        std::fs::write(&output_path, svg_content).map_err(|e| e.to_string())?;
+        if let Err(e) = std::fs::write(&temp_path, svg_content) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e.to_string());
+        }
+        std::fs::rename(&temp_path, &output_path).map_err(|e| e.to_string())?;
         return Ok(output_path_str);
     }
 
-    args.push(&output_path_str);
+    let temp_path_str = temp_path.to_string_lossy().to_string();
 
-    let output = app.shell().command("ffmpeg")
+    args.extend(options.metadata_args(output_format));
+    args.extend(options.ffmpeg_args(output_format));
+    args.push(temp_path_str);
+
+    // -progress pipe:1 writes machine-readable key=value lines to stdout as the
+    // conversion runs; -nostats suppresses the human-readable stderr status line.
+    args.push("-progress".to_string());
+    args.push("pipe:1".to_string());
+    args.push("-nostats".to_string());
+
+    let duration_secs = probe_duration_secs(app, file_path).await.unwrap_or(0.0);
+    let mut parser = ProgressParser::new(job_id.to_string(), duration_secs);
+
+    let (mut rx, child) = app
+        .shell()
+        .command("ffmpeg")
         .args(args)
-        .output()
-        .await
+        .spawn()
         .map_err(|e| e.to_string())?;
 
-    if output.status.success() {
-        Ok(output_path_str)
-    } else {
-        Err(format!("FFmpeg failed: {}", String::from_utf8_lossy(&output.stderr)))
+    if let Some(queue) = queue {
+        queue.register_child(job_id, child).await;
     }
+
+    let mut stdout_buf = Vec::new();
+    let mut stderr_buf = Vec::new();
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(chunk) => {
+                stdout_buf.extend_from_slice(&chunk);
+                while let Some(pos) = stdout_buf.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = stdout_buf.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    if let Some(update) = parser.feed_line(line.trim_end()) {
+                        let _ = app.emit("conversion-progress", update);
+                    }
+                }
+            }
+            CommandEvent::Stderr(chunk) => stderr_buf.extend_from_slice(&chunk),
+            CommandEvent::Terminated(payload) => {
+                if payload.code != Some(0) {
+                    let _ = std::fs::remove_file(&temp_path);
+                    return Err(format!("FFmpeg failed: {}", String::from_utf8_lossy(&stderr_buf)));
+                }
+            }
+            CommandEvent::Error(message) => {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(message);
+            }
+            _ => {}
+        }
+    }
+
+    std::fs::rename(&temp_path, &output_path).map_err(|e| e.to_string())?;
+    Ok(output_path_str)
 }
 
 /// Saves a file from a temporary location to a user-selected destination.
@@ -104,7 +252,18 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![greet, convert_file, save_file_locally])
+        .manage(QueueState::new())
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            convert_file,
+            save_file_locally,
+            formats::list_supported_formats,
+            queue::enqueue_conversions,
+            queue::cancel_conversion,
+            queue::get_queue_status,
+            image_info::read_image_metadata,
+            image_info::generate_thumbnail
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,125 @@
+use serde::Deserialize;
+
+use crate::formats::MediaKind;
+
+/// Per-format quality/compression knobs passed into [`crate::convert_file`]. Each
+/// field is only consulted for the output formats it applies to; everything else
+/// keeps FFmpeg's defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConversionOptions {
+    /// `-q:v` for JPEG output, FFmpeg's 1 (best) - 31 (worst) qscale.
+    pub jpeg_quality: Option<u8>,
+    /// `-lossless 1` for WebP output.
+    pub webp_lossless: Option<bool>,
+    /// `-compression_level` for PNG output, 0 (fastest) - 9 (smallest).
+    pub png_compression_level: Option<u8>,
+    /// `-b:a` for audio output, in kbps.
+    pub audio_bitrate_kbps: Option<u32>,
+    /// `-crf` for video output, lower is higher quality.
+    pub video_crf: Option<u8>,
+    /// `-preset` for video output (e.g. "fast", "slow").
+    pub video_preset: Option<String>,
+    /// Strip EXIF/GPS/XMP metadata (`-map_metadata -1`) from image output. Defaults
+    /// to `true` so converted images shared from the app don't leak camera/location data.
+    pub strip_metadata: bool,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            jpeg_quality: None,
+            webp_lossless: None,
+            png_compression_level: None,
+            audio_bitrate_kbps: None,
+            video_crf: None,
+            video_preset: None,
+            strip_metadata: true,
+        }
+    }
+}
+
+impl ConversionOptions {
+    /// Translates the options relevant to `output_format` into FFmpeg CLI flags.
+    pub fn ffmpeg_args(&self, output_format: &str) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match output_format.to_lowercase().as_str() {
+            "jpg" | "jpeg" => {
+                if let Some(quality) = self.jpeg_quality {
+                    args.push("-q:v".to_string());
+                    args.push(quality.to_string());
+                }
+            }
+            "webp" => {
+                if self.webp_lossless == Some(true) {
+                    args.push("-lossless".to_string());
+                    args.push("1".to_string());
+                }
+            }
+            "png" => {
+                if let Some(level) = self.png_compression_level {
+                    args.push("-compression_level".to_string());
+                    args.push(level.to_string());
+                }
+            }
+            "mp3" | "aac" | "m4a" | "ogg" | "flac" | "wav" => {
+                if let Some(bitrate) = self.audio_bitrate_kbps {
+                    args.push("-b:a".to_string());
+                    args.push(format!("{}k", bitrate));
+                }
+            }
+            // mp4/mkv/mov and webm don't share a default video codec that accepts
+            // -crf/-preset (avi's default mpeg4 encoder accepts neither), so force
+            // an explicit codec here rather than trusting the muxer's default.
+            "mp4" | "mkv" | "mov" => {
+                args.push("-c:v".to_string());
+                args.push("libx264".to_string());
+                if let Some(crf) = self.video_crf {
+                    args.push("-crf".to_string());
+                    args.push(crf.to_string());
+                }
+                if let Some(preset) = &self.video_preset {
+                    args.push("-preset".to_string());
+                    args.push(preset.clone());
+                }
+            }
+            "webm" => {
+                args.push("-c:v".to_string());
+                args.push("libvpx-vp9".to_string());
+                if let Some(crf) = self.video_crf {
+                    // libvpx-vp9 treats -crf as constant-quality only alongside -b:v 0.
+                    args.push("-crf".to_string());
+                    args.push(crf.to_string());
+                    args.push("-b:v".to_string());
+                    args.push("0".to_string());
+                }
+                // libvpx-vp9 has no -preset option; video_preset is ignored for webm.
+            }
+            _ => {}
+        }
+
+        args
+    }
+
+    /// Stable string identifying these options, folded into the cache key so that
+    /// two different quality settings for the same input don't collide.
+    pub fn cache_fingerprint(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// Whether this conversion will strip metadata from `output_format`'s output.
+    /// Only image formats carry EXIF/GPS/XMP metadata worth stripping.
+    pub fn strips_metadata(&self, output_format: &str) -> bool {
+        self.strip_metadata && matches!(MediaKind::from_extension(output_format), Some(MediaKind::Image))
+    }
+
+    /// FFmpeg flags for metadata handling, derived from [`Self::strips_metadata`].
+    pub fn metadata_args(&self, output_format: &str) -> Vec<String> {
+        if self.strips_metadata(output_format) {
+            vec!["-map_metadata".to_string(), "-1".to_string()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+/// Progress update emitted to the frontend while FFmpeg is converting a file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionProgress {
+    pub job_id: String,
+    pub percent: f64,
+    pub speed: Option<String>,
+    pub eta_secs: Option<f64>,
+}
+
+/// Emitted once a conversion finishes successfully.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionDone {
+    pub job_id: String,
+    pub output_path: String,
+    pub metadata_stripped: bool,
+}
+
+/// Emitted when a conversion fails.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionError {
+    pub job_id: String,
+    pub message: String,
+}
+
+/// Emitted when something about a conversion is worth flagging without failing it,
+/// e.g. the file's extension disagreeing with its sniffed content type.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionWarning {
+    pub job_id: String,
+    pub message: String,
+}
+
+/// Incrementally parses the `key=value` lines produced by `ffmpeg -progress pipe:1`,
+/// turning them into [`ConversionProgress`] updates once per `progress=` terminator line.
+pub struct ProgressParser {
+    job_id: String,
+    duration_secs: f64,
+    out_time_ms: Option<u64>,
+    speed: Option<String>,
+}
+
+impl ProgressParser {
+    pub fn new(job_id: String, duration_secs: f64) -> Self {
+        Self { job_id, duration_secs, out_time_ms: None, speed: None }
+    }
+
+    /// Feeds one line of FFmpeg's progress output. Returns `Some` once a complete
+    /// `progress=continue`/`progress=end` group has been accumulated.
+    pub fn feed_line(&mut self, line: &str) -> Option<ConversionProgress> {
+        let (key, value) = line.split_once('=')?;
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "out_time_ms" => self.out_time_ms = value.parse().ok(),
+            "speed" => self.speed = Some(value.to_string()),
+            "progress" => return Some(self.snapshot()),
+            _ => {}
+        }
+        None
+    }
+
+    fn snapshot(&self) -> ConversionProgress {
+        let elapsed_secs = self.out_time_ms.unwrap_or(0) as f64 / 1_000_000.0;
+        let percent = if self.duration_secs > 0.0 {
+            (elapsed_secs / self.duration_secs * 100.0).clamp(0.0, 100.0)
+        } else {
+            0.0
+        };
+
+        let eta_secs = self.speed.as_deref().and_then(parse_speed_factor).and_then(|factor| {
+            if factor <= 0.0 {
+                return None;
+            }
+            let remaining_secs = (self.duration_secs - elapsed_secs).max(0.0);
+            Some(remaining_secs / factor)
+        });
+
+        ConversionProgress {
+            job_id: self.job_id.clone(),
+            percent,
+            speed: self.speed.clone(),
+            eta_secs,
+        }
+    }
+}
+
+/// Parses FFmpeg's `speed=1.23x` field into a plain multiplier.
+fn parse_speed_factor(speed: &str) -> Option<f64> {
+    speed.trim().trim_end_matches('x').parse().ok()
+}
+
+/// Parses the duration (in seconds) out of `ffprobe`'s
+/// `-show_entries format=duration -of default=noprint_wrapper=1:nokey=1` output.
+pub fn parse_duration_output(stdout: &str) -> Option<f64> {
+    stdout.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feed_line_ignores_unterminated_key_value_lines() {
+        let mut parser = ProgressParser::new("job-1".to_string(), 10.0);
+        assert!(parser.feed_line("out_time_ms=5000000").is_none());
+        assert!(parser.feed_line("speed=2.0x").is_none());
+    }
+
+    #[test]
+    fn feed_line_emits_a_snapshot_on_the_progress_terminator() {
+        let mut parser = ProgressParser::new("job-1".to_string(), 10.0);
+        parser.feed_line("out_time_ms=5000000"); // 5,000,000 microseconds = 5s elapsed
+        parser.feed_line("speed=2.0x");
+        let update = parser.feed_line("progress=continue").expect("progress line should emit");
+
+        assert_eq!(update.job_id, "job-1");
+        assert_eq!(update.percent, 50.0);
+        assert_eq!(update.speed.as_deref(), Some("2.0x"));
+        assert_eq!(update.eta_secs, Some(2.5)); // 5s remaining at 2x speed
+    }
+
+    #[test]
+    fn zero_duration_reports_zero_percent_and_does_not_panic() {
+        let mut parser = ProgressParser::new("job-1".to_string(), 0.0);
+        parser.feed_line("out_time_ms=5000000");
+        let update = parser.feed_line("progress=end").unwrap();
+        assert_eq!(update.percent, 0.0);
+        assert_eq!(update.eta_secs, None);
+    }
+
+    #[test]
+    fn parse_duration_output_trims_whitespace() {
+        assert_eq!(parse_duration_output("12.345000\n"), Some(12.345));
+        assert_eq!(parse_duration_output("N/A"), None);
+    }
+}
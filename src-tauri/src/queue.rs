@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_shell::process::CommandChild;
+use tokio::sync::{Mutex, Semaphore};
+use uuid::Uuid;
+
+use crate::options::ConversionOptions;
+use crate::progress::{ConversionDone, ConversionError};
+use crate::run_conversion;
+
+/// Maximum number of FFmpeg conversions the queue will run at the same time.
+const MAX_CONCURRENT_CONVERSIONS: usize = 2;
+
+/// One file/format pair submitted to [`enqueue_conversions`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConversionRequest {
+    pub file_path: String,
+    pub output_format: String,
+    #[serde(default)]
+    pub options: ConversionOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+/// Point-in-time state of one queued conversion, as returned by [`get_queue_status`].
+#[derive(Debug, Clone, Serialize)]
+pub struct JobState {
+    pub job_id: String,
+    pub file_path: String,
+    pub output_format: String,
+    pub status: JobStatus,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+    pub metadata_stripped: bool,
+}
+
+/// Tracks every job the queue has accepted and a handle to each job's running
+/// FFmpeg child process, so that a cancel request can kill it.
+pub struct QueueState {
+    jobs: Mutex<HashMap<String, JobState>>,
+    children: Mutex<HashMap<String, CommandChild>>,
+    limit: Semaphore,
+}
+
+impl QueueState {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            children: Mutex::new(HashMap::new()),
+            limit: Semaphore::new(MAX_CONCURRENT_CONVERSIONS),
+        }
+    }
+
+    async fn insert(&self, job: JobState) {
+        self.jobs.lock().await.insert(job.job_id.clone(), job);
+    }
+
+    async fn update(&self, job_id: &str, f: impl FnOnce(&mut JobState)) {
+        if let Some(job) = self.jobs.lock().await.get_mut(job_id) {
+            f(job);
+        }
+    }
+
+    async fn status(&self, job_id: &str) -> Option<JobStatus> {
+        self.jobs.lock().await.get(job_id).map(|job| job.status)
+    }
+
+    /// Stores the running FFmpeg child for `job_id` so it can be killed on cancellation.
+    pub(crate) async fn register_child(&self, job_id: &str, child: CommandChild) {
+        self.children.lock().await.insert(job_id.to_string(), child);
+    }
+
+    async fn take_child(&self, job_id: &str) -> Option<CommandChild> {
+        self.children.lock().await.remove(job_id)
+    }
+}
+
+/// Accepts a batch of `(file_path, output_format)` pairs and converts them with a
+/// bounded concurrency limit, returning the job id assigned to each.
+#[tauri::command]
+pub async fn enqueue_conversions(
+    app: AppHandle,
+    state: State<'_, QueueState>,
+    jobs: Vec<ConversionRequest>,
+) -> Result<Vec<String>, String> {
+    let mut job_ids = Vec::with_capacity(jobs.len());
+
+    for request in jobs {
+        let job_id = Uuid::new_v4().to_string();
+        state
+            .insert(JobState {
+                job_id: job_id.clone(),
+                file_path: request.file_path.clone(),
+                output_format: request.output_format.clone(),
+                status: JobStatus::Queued,
+                output_path: None,
+                error: None,
+                metadata_stripped: false,
+            })
+            .await;
+        job_ids.push(job_id.clone());
+
+        let app = app.clone();
+        tokio::spawn(async move {
+            let state = app.state::<QueueState>();
+            let _permit = state.limit.acquire().await.expect("queue semaphore closed");
+
+            // The job may have been cancelled while it was still waiting for a permit.
+            if state.status(&job_id).await == Some(JobStatus::Cancelled) {
+                return;
+            }
+
+            state.update(&job_id, |job| job.status = JobStatus::Running).await;
+
+            let result = run_conversion(&app, &job_id, &request.file_path, &request.output_format, &request.options, Some(&state)).await;
+            state.take_child(&job_id).await;
+
+            match result {
+                Ok(output_path) => {
+                    // The svg branch embeds the source bytes verbatim as a data: URI rather
+                    // than going through FFmpeg's -map_metadata, so nothing is actually
+                    // stripped there (mirrors convert_file's single-file path in lib.rs).
+                    let metadata_stripped = request.output_format.to_lowercase() != "svg"
+                        && request.options.strips_metadata(&request.output_format);
+                    state
+                        .update(&job_id, |job| {
+                            job.status = JobStatus::Done;
+                            job.output_path = Some(output_path.clone());
+                            job.metadata_stripped = metadata_stripped;
+                        })
+                        .await;
+                    let _ = app.emit("conversion-done", ConversionDone { job_id: job_id.clone(), output_path, metadata_stripped });
+                }
+                Err(message) => {
+                    state
+                        .update(&job_id, |job| {
+                            // A cancellation already set the terminal status; don't overwrite it.
+                            if job.status != JobStatus::Cancelled {
+                                job.status = JobStatus::Failed;
+                                job.error = Some(message.clone());
+                            }
+                        })
+                        .await;
+                    let _ = app.emit("conversion-error", ConversionError { job_id: job_id.clone(), message });
+                }
+            }
+        });
+    }
+
+    Ok(job_ids)
+}
+
+/// Kills the FFmpeg process backing `job_id`, if it is still running.
+#[tauri::command]
+pub async fn cancel_conversion(state: State<'_, QueueState>, job_id: String) -> Result<(), String> {
+    if let Some(child) = state.take_child(&job_id).await {
+        child.kill().map_err(|e| e.to_string())?;
+    }
+    state.update(&job_id, |job| job.status = JobStatus::Cancelled).await;
+    Ok(())
+}
+
+/// Returns the current state of every job the queue has accepted.
+#[tauri::command]
+pub async fn get_queue_status(state: State<'_, QueueState>) -> Result<Vec<JobState>, String> {
+    Ok(state.jobs.lock().await.values().cloned().collect())
+}